@@ -3,7 +3,26 @@ use glob::glob;
 use hcl::Value;
 use serde::Serialize;
 use serde_json::Value as JsonValue;
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ArrayMergeStrategy {
+    #[default]
+    Replace,
+    Append,
+    Union,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Json,
+    Yaml,
+    Toml,
+    Nix,
+}
 
 pub struct Config {
     pub pretty: bool,
@@ -12,21 +31,34 @@ pub struct Config {
     pub single_quotes: bool,
     pub files: Vec<String>,
     pub deep_merge: bool,
+    pub strict: bool,
+    pub array_merge: ArrayMergeStrategy,
+    pub set: Vec<String>,
+    pub resolve_includes: bool,
+    pub to: OutputFormat,
     pub property: Option<String>,
 }
 
 pub fn process_hcl(config: Config, input: Option<String>) -> Result<String> {
     if config.validate {
-        return validate_files(&config.files, input);
+        return validate_files(&config.files, input, config.resolve_includes);
     }
 
     let merged_value = if config.files.is_empty() {
         let content = input.context("No input provided")?;
-        parse_hcl_content(&content)?
+        parse_hcl_content(&content, Path::new("."), config.resolve_includes)?
     } else {
-        merge_files(&config.files, config.deep_merge)?
+        merge_files(
+            &config.files,
+            config.deep_merge,
+            config.strict,
+            config.array_merge,
+            config.resolve_includes,
+        )?
     };
 
+    let merged_value = apply_overrides(merged_value, &config.set)?;
+
     let result_value = if let Some(property) = &config.property {
         extract_property(&merged_value, property)?
     } else {
@@ -35,16 +67,17 @@ pub fn process_hcl(config: Config, input: Option<String>) -> Result<String> {
 
     format_output(
         &result_value,
+        config.to,
         config.pretty,
         config.indent,
         config.single_quotes,
     )
 }
 
-fn validate_files(files: &[String], input: Option<String>) -> Result<String> {
+fn validate_files(files: &[String], input: Option<String>, resolve_includes_flag: bool) -> Result<String> {
     if files.is_empty() {
         if let Some(content) = input {
-            parse_hcl_content(&content)?;
+            parse_hcl_content(&content, Path::new("."), resolve_includes_flag)?;
             return Ok("VALID: stdin".to_string());
         }
         bail!("No files or input provided for validation");
@@ -55,27 +88,37 @@ fn validate_files(files: &[String], input: Option<String>) -> Result<String> {
         for entry in glob(file_pattern)? {
             let path = entry?;
             let content = fs::read_to_string(&path)?;
-            parse_hcl_content(&content)?;
+            let base_dir = path.parent().unwrap_or(Path::new("."));
+            parse_hcl_content(&content, base_dir, resolve_includes_flag)?;
             results.push(format!("VALID: {}", path.display()));
         }
     }
     Ok(results.join("\n"))
 }
 
-fn merge_files(files: &[String], deep_merge: bool) -> Result<JsonValue> {
+fn merge_files(
+    files: &[String],
+    deep_merge: bool,
+    strict: bool,
+    array_merge: ArrayMergeStrategy,
+    resolve_includes_flag: bool,
+) -> Result<JsonValue> {
     let mut merged = JsonValue::Object(serde_json::Map::new());
+    let mut origins: HashMap<String, String> = HashMap::new();
 
     for file_pattern in files {
         for entry in glob(file_pattern)? {
             let path = entry?;
             let content = fs::read_to_string(&path)
                 .with_context(|| format!("Failed to read file: {}", path.display()))?;
-            let value = parse_hcl_content(&content)?;
+            let base_dir = path.parent().unwrap_or(Path::new("."));
+            let value = parse_hcl_content(&content, base_dir, resolve_includes_flag)?;
+            let source = path.display().to_string();
 
             if deep_merge {
-                deep_merge_json(&mut merged, value);
+                deep_merge_json(&mut merged, value, "", &source, &mut origins, strict, array_merge)?;
             } else {
-                shallow_merge_json(&mut merged, value);
+                shallow_merge_json(&mut merged, value, &source, &mut origins, strict)?;
             }
         }
     }
@@ -83,12 +126,152 @@ fn merge_files(files: &[String], deep_merge: bool) -> Result<JsonValue> {
     Ok(merged)
 }
 
-fn parse_hcl_content(content: &str) -> Result<JsonValue> {
+fn merge_arrays(target: &mut Vec<JsonValue>, source: Vec<JsonValue>, strategy: ArrayMergeStrategy) {
+    match strategy {
+        ArrayMergeStrategy::Replace => *target = source,
+        ArrayMergeStrategy::Append => target.extend(source),
+        ArrayMergeStrategy::Union => {
+            for item in source {
+                if !target.iter().any(|existing| existing == &item) {
+                    target.push(item);
+                }
+            }
+        }
+    }
+}
+
+fn parse_hcl_content(
+    content: &str,
+    base_dir: &Path,
+    resolve_includes_flag: bool,
+) -> Result<JsonValue> {
     let hcl_value: Value = hcl::from_str(content).context("Failed to parse HCL content")?;
 
     let json_string = serde_json::to_string(&hcl_value).context("Failed to convert HCL to JSON")?;
 
-    serde_json::from_str(&json_string).context("Failed to parse JSON")
+    let value: JsonValue = serde_json::from_str(&json_string).context("Failed to parse JSON")?;
+
+    if resolve_includes_flag {
+        resolve_includes(value, base_dir)
+    } else {
+        Ok(value)
+    }
+}
+
+fn resolve_includes(value: JsonValue, base_dir: &Path) -> Result<JsonValue> {
+    let mut visited = HashSet::new();
+    resolve_includes_inner(value, base_dir, &mut visited)
+}
+
+fn resolve_includes_inner(
+    value: JsonValue,
+    base_dir: &Path,
+    visited: &mut HashSet<PathBuf>,
+) -> Result<JsonValue> {
+    let mut obj = match value {
+        JsonValue::Object(obj) => obj,
+        other => return Ok(other),
+    };
+
+    let includes = match obj.remove("include") {
+        Some(JsonValue::Array(paths)) => paths,
+        Some(_) => bail!("'include' must be an array of file paths"),
+        None => return Ok(JsonValue::Object(obj)),
+    };
+
+    let mut merged = JsonValue::Object(serde_json::Map::new());
+    let mut origins: HashMap<String, String> = HashMap::new();
+
+    for include_path in includes {
+        let include_path = include_path
+            .as_str()
+            .context("'include' entries must be strings")?;
+        let resolved = base_dir.join(include_path);
+        let canonical = fs::canonicalize(&resolved)
+            .with_context(|| format!("Failed to resolve include: {}", resolved.display()))?;
+
+        if !visited.insert(canonical.clone()) {
+            bail!("Include cycle detected at {}", resolved.display());
+        }
+
+        let include_content = fs::read_to_string(&resolved)
+            .with_context(|| format!("Failed to read included file: {}", resolved.display()))?;
+        let include_base = resolved.parent().unwrap_or(Path::new("."));
+        let include_value = parse_hcl_content(&include_content, include_base, false)?;
+        let include_value = resolve_includes_inner(include_value, include_base, visited)?;
+
+        visited.remove(&canonical);
+
+        deep_merge_json(
+            &mut merged,
+            include_value,
+            "",
+            include_path,
+            &mut origins,
+            false,
+            ArrayMergeStrategy::Replace,
+        )?;
+    }
+
+    deep_merge_json(
+        &mut merged,
+        JsonValue::Object(obj),
+        "",
+        "(including file)",
+        &mut origins,
+        false,
+        ArrayMergeStrategy::Replace,
+    )?;
+
+    Ok(merged)
+}
+
+fn apply_overrides(mut value: JsonValue, overrides: &[String]) -> Result<JsonValue> {
+    if overrides.is_empty() {
+        return Ok(value);
+    }
+
+    let map = value
+        .as_object_mut()
+        .context("Cannot apply --set overrides to non-object content")?;
+
+    for entry in overrides {
+        let (key, raw_value) = entry
+            .split_once('=')
+            .with_context(|| format!("Invalid --set argument (expected key=value): {}", entry))?;
+        let path: Vec<&str> = key.split('.').collect();
+        nested_set(map, &path, parse_set_value(raw_value));
+    }
+
+    Ok(value)
+}
+
+fn parse_set_value(raw: &str) -> JsonValue {
+    serde_json::from_str(raw).unwrap_or_else(|_| JsonValue::String(raw.to_string()))
+}
+
+fn nested_set(map: &mut serde_json::Map<String, JsonValue>, path: &[&str], value: JsonValue) {
+    let (head, rest) = match path.split_first() {
+        Some(parts) => parts,
+        None => return,
+    };
+
+    if rest.is_empty() {
+        map.insert((*head).to_string(), value);
+        return;
+    }
+
+    let entry = map
+        .entry((*head).to_string())
+        .or_insert_with(|| JsonValue::Object(serde_json::Map::new()));
+
+    if !entry.is_object() {
+        *entry = JsonValue::Object(serde_json::Map::new());
+    }
+
+    if let JsonValue::Object(nested_map) = entry {
+        nested_set(nested_map, rest, value);
+    }
 }
 
 fn extract_property(value: &JsonValue, property: &str) -> Result<JsonValue> {
@@ -125,35 +308,130 @@ fn extract_property(value: &JsonValue, property: &str) -> Result<JsonValue> {
     Ok(current.clone())
 }
 
-fn deep_merge_json(target: &mut JsonValue, source: JsonValue) {
+fn deep_merge_json(
+    target: &mut JsonValue,
+    source: JsonValue,
+    path: &str,
+    source_file: &str,
+    origins: &mut HashMap<String, String>,
+    strict: bool,
+    array_merge: ArrayMergeStrategy,
+) -> Result<()> {
     match (target, source) {
         (JsonValue::Object(target_map), JsonValue::Object(source_map)) => {
             for (key, value) in source_map {
+                let key_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+
                 if let Some(existing_value) = target_map.get_mut(&key) {
-                    deep_merge_json(existing_value, value);
+                    deep_merge_json(
+                        existing_value,
+                        value,
+                        &key_path,
+                        source_file,
+                        origins,
+                        strict,
+                        array_merge,
+                    )?;
                 } else {
+                    register_origins(&value, &key_path, source_file, origins);
                     target_map.insert(key, value);
                 }
             }
+            Ok(())
+        }
+        (JsonValue::Array(target_arr), JsonValue::Array(source_arr))
+            if array_merge != ArrayMergeStrategy::Replace =>
+        {
+            origins.insert(path.to_string(), source_file.to_string());
+            merge_arrays(target_arr, source_arr, array_merge);
+            Ok(())
+        }
+        (target, source) => {
+            if *target != source {
+                if strict {
+                    let original_file = origins
+                        .get(path)
+                        .map(String::as_str)
+                        .unwrap_or("<unknown>");
+                    bail!(
+                        "conflicting values for '{}' in {} and {}",
+                        path, original_file, source_file
+                    );
+                }
+                origins.insert(path.to_string(), source_file.to_string());
+            }
+            *target = source;
+            Ok(())
         }
-        (target, source) => *target = source,
     }
 }
 
-fn shallow_merge_json(target: &mut JsonValue, source: JsonValue) {
+fn register_origins(
+    value: &JsonValue,
+    path: &str,
+    source_file: &str,
+    origins: &mut HashMap<String, String>,
+) {
+    origins.insert(path.to_string(), source_file.to_string());
+
+    if let JsonValue::Object(map) = value {
+        for (key, child) in map {
+            let child_path = format!("{}.{}", path, key);
+            register_origins(child, &child_path, source_file, origins);
+        }
+    }
+}
+
+fn shallow_merge_json(
+    target: &mut JsonValue,
+    source: JsonValue,
+    source_file: &str,
+    origins: &mut HashMap<String, String>,
+    strict: bool,
+) -> Result<()> {
     if let (JsonValue::Object(target_map), JsonValue::Object(source_map)) = (target, source) {
         for (key, value) in source_map {
+            if strict {
+                if let Some(existing) = target_map.get(&key) {
+                    if existing != &value {
+                        let original_file = origins
+                            .get(&key)
+                            .map(String::as_str)
+                            .unwrap_or("<unknown>");
+                        bail!(
+                            "conflicting values for '{}' in {} and {}",
+                            key, original_file, source_file
+                        );
+                    }
+                }
+            }
+            origins.insert(key.clone(), source_file.to_string());
             target_map.insert(key, value);
         }
     }
+    Ok(())
 }
 
 fn format_output(
     value: &JsonValue,
+    to: OutputFormat,
     pretty: bool,
     indent: usize,
     single_quotes: bool,
 ) -> Result<String> {
+    match to {
+        OutputFormat::Json => format_json(value, pretty, indent, single_quotes),
+        OutputFormat::Yaml => serde_yaml::to_string(value).context("Failed to serialize to YAML"),
+        OutputFormat::Toml => toml::to_string(value).context("Failed to serialize to TOML"),
+        OutputFormat::Nix => Ok(format_nix(value, 0)),
+    }
+}
+
+fn format_json(value: &JsonValue, pretty: bool, indent: usize, single_quotes: bool) -> Result<String> {
     let json_string = if pretty {
         let indent_bytes = vec![b' '; indent];
         let formatter = serde_json::ser::PrettyFormatter::with_indent(&indent_bytes);
@@ -171,3 +449,44 @@ fn format_output(
         Ok(json_string)
     }
 }
+
+fn format_nix(value: &JsonValue, indent: usize) -> String {
+    match value {
+        JsonValue::Null => "null".to_string(),
+        JsonValue::Bool(b) => b.to_string(),
+        JsonValue::Number(n) => n.to_string(),
+        JsonValue::String(s) => format!("\"{}\"", escape_nix_string(s)),
+        JsonValue::Array(arr) => {
+            if arr.is_empty() {
+                "[ ]".to_string()
+            } else {
+                let inner_indent = "  ".repeat(indent + 1);
+                let items: Vec<String> = arr
+                    .iter()
+                    .map(|item| format!("{}{}", inner_indent, format_nix(item, indent + 1)))
+                    .collect();
+                format!("[\n{}\n{}]", items.join("\n"), "  ".repeat(indent))
+            }
+        }
+        JsonValue::Object(obj) => {
+            if obj.is_empty() {
+                "{ }".to_string()
+            } else {
+                let inner_indent = "  ".repeat(indent + 1);
+                let items: Vec<String> = obj
+                    .iter()
+                    .map(|(k, v)| format!("{}{} = {};", inner_indent, k, format_nix(v, indent + 1)))
+                    .collect();
+                format!("{{\n{}\n{}}}", items.join("\n"), "  ".repeat(indent))
+            }
+        }
+    }
+}
+
+fn escape_nix_string(s: &str) -> String {
+    // `$` must be escaped too, or a literal `${...}` (common in HCL/Terraform
+    // template strings) is interpreted by Nix as string interpolation.
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('$', "\\$")
+}
@@ -3,9 +3,27 @@ use clap::Parser;
 use glob::glob;
 use hcl::Value;
 use serde_json::Value as JsonValue;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io::{self, Read};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, clap::ValueEnum)]
+enum ArrayMergeStrategy {
+    #[default]
+    Replace,
+    Append,
+    Union,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, clap::ValueEnum)]
+enum OutputFormat {
+    #[default]
+    Json,
+    Yaml,
+    Toml,
+    Nix,
+}
 
 #[derive(Parser)]
 #[command(name = "hcl2json")]
@@ -35,10 +53,43 @@ struct Args {
     #[arg(short, long, value_name = "FILE")]
     file: Vec<String>,
 
+    /// Read a newline-delimited list of additional HCL files (or glob patterns) to convert/merge
+    #[arg(long, value_name = "PATH")]
+    from_file: Option<PathBuf>,
+
+    /// Write a Make-style dependency file listing the output target and every input file
+    /// matched by --file/--from-file (files pulled in by --resolve-includes are not listed)
+    #[arg(long, value_name = "PATH")]
+    depfile: Option<PathBuf>,
+
     /// Use deep merge instead of shallow merge when multiple files provided
     #[arg(long)]
     deep_merge: bool,
 
+    /// Abort with an error if merged files set conflicting values for the same key
+    /// instead of letting the later file silently win
+    #[arg(long)]
+    strict_merge: bool,
+
+    /// How to combine array values present in more than one merged file
+    /// (only applies with --deep-merge; a shallow merge replaces them)
+    #[arg(long, value_enum, default_value_t = ArrayMergeStrategy::Replace)]
+    array_merge: ArrayMergeStrategy,
+
+    /// Override a value at a dotted key path after parsing/merging (repeatable),
+    /// e.g. --set database.port=5432
+    #[arg(long, value_name = "KEY=VALUE")]
+    set: Vec<String>,
+
+    /// Resolve a top-level `include = [...]` key in each HCL file, recursively merging
+    /// the listed files (paths relative to the including file) as defaults
+    #[arg(long)]
+    resolve_includes: bool,
+
+    /// Output format (--pretty/--indent/--single-quotes only apply to json)
+    #[arg(long, value_enum, default_value_t = OutputFormat::Json)]
+    to: OutputFormat,
+
     /// Property within HCL to extract (optional)
     #[arg(short, long)]
     property: Option<String>,
@@ -56,7 +107,7 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
-    let files = get_input_files(&args.file)?;
+    let files = get_input_files(&args.file, args.from_file.as_ref())?;
     let contents = read_files(&files)?;
 
     if args.validate {
@@ -64,46 +115,77 @@ fn main() -> Result<()> {
     }
 
     let json_value = if contents.len() > 1 {
-        merge_hcl_contents(&contents, args.deep_merge)?
+        merge_hcl_contents(
+            &contents,
+            args.deep_merge,
+            args.strict_merge,
+            args.array_merge,
+            args.resolve_includes,
+        )?
     } else if contents.len() == 1 {
-        parse_hcl_content(&contents[0])?
+        parse_hcl_content(&contents[0], args.resolve_includes)?
     } else {
         bail!("No input provided");
     };
 
+    let json_value = apply_overrides(json_value, &args.set)?;
+
     let final_value = if let Some(property) = &args.property {
         extract_property(&json_value, property)?
     } else {
         json_value
     };
 
-    let json_string = format_json(&final_value, &args)?;
+    let formatted = format_output(&final_value, &args)?;
 
-    let output = if args.single_quotes {
+    let output = if args.to == OutputFormat::Json && args.single_quotes {
         // Replace JSON structure quotes but preserve escaped quotes in values
-        json_string
+        formatted
             .replace("\\\"", "ESCAPED_QUOTE_PLACEHOLDER")
             .replace('"', "'")
             .replace("ESCAPED_QUOTE_PLACEHOLDER", "\\\"")
     } else {
-        json_string
+        formatted
     };
 
-    match args.output {
+    match &args.output {
         Some(path) => fs::write(path, output)?,
         None => println!("{}", output),
     }
 
+    if let Some(depfile_path) = &args.depfile {
+        let target = args
+            .output
+            .as_ref()
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|| "-".to_string());
+        write_depfile(depfile_path, &target, &files)?;
+    }
+
     Ok(())
 }
 
-fn get_input_files(file_patterns: &[String]) -> Result<Vec<PathBuf>> {
-    if file_patterns.is_empty() {
+fn get_input_files(file_patterns: &[String], from_file: Option<&PathBuf>) -> Result<Vec<PathBuf>> {
+    let mut patterns = file_patterns.to_vec();
+
+    if let Some(manifest) = from_file {
+        let manifest_content = fs::read_to_string(manifest).with_context(|| {
+            format!("Failed to read --from-file manifest: {}", manifest.display())
+        })?;
+        for line in manifest_content.lines() {
+            let line = line.trim();
+            if !line.is_empty() {
+                patterns.push(line.to_string());
+            }
+        }
+    }
+
+    if patterns.is_empty() {
         return Ok(vec![]);
     }
 
     let mut files = Vec::new();
-    for pattern in file_patterns {
+    for pattern in &patterns {
         let matches =
             glob(pattern).with_context(|| format!("Invalid glob pattern: {}", pattern))?;
 
@@ -121,6 +203,17 @@ fn get_input_files(file_patterns: &[String]) -> Result<Vec<PathBuf>> {
     Ok(files)
 }
 
+fn write_depfile(depfile_path: &Path, target: &str, files: &[PathBuf]) -> Result<()> {
+    let deps = files
+        .iter()
+        .map(|f| f.display().to_string())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    fs::write(depfile_path, format!("{}: {}\n", target, deps))
+        .with_context(|| format!("Failed to write depfile: {}", depfile_path.display()))
+}
+
 fn read_files(files: &[PathBuf]) -> Result<Vec<(String, String)>> {
     if files.is_empty() {
         let mut buffer = String::new();
@@ -160,7 +253,10 @@ fn validate_hcl_files(contents: &[(String, String)]) -> Result<()> {
     Ok(())
 }
 
-fn parse_hcl_content((name, content): &(String, String)) -> Result<JsonValue> {
+fn parse_hcl_content(
+    (name, content): &(String, String),
+    resolve_includes_flag: bool,
+) -> Result<JsonValue> {
     let hcl_value: Value = hcl::from_str(content).with_context(|| {
         format!(
             "Failed to parse HCL in {}: {}",
@@ -169,25 +265,45 @@ fn parse_hcl_content((name, content): &(String, String)) -> Result<JsonValue> {
         )
     })?;
 
-    hcl_to_json(hcl_value)
+    let json_value = hcl_to_json(hcl_value)?;
+
+    if resolve_includes_flag {
+        resolve_includes(json_value, base_dir_of(name))
+    } else {
+        Ok(json_value)
+    }
 }
 
-fn merge_hcl_contents(contents: &[(String, String)], deep: bool) -> Result<JsonValue> {
+fn base_dir_of(name: &str) -> &Path {
+    Path::new(name).parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."))
+}
+
+fn merge_hcl_contents(
+    contents: &[(String, String)],
+    deep: bool,
+    strict: bool,
+    array_merge: ArrayMergeStrategy,
+    resolve_includes_flag: bool,
+) -> Result<JsonValue> {
     let mut merged = serde_json::Map::new();
+    let mut origins: HashMap<String, String> = HashMap::new();
 
     for (name, content) in contents {
         let hcl_value: Value =
             hcl::from_str(content).with_context(|| format!("Failed to parse HCL in {}", name))?;
 
         let json_value = hcl_to_json(hcl_value)?;
+        let json_value = if resolve_includes_flag {
+            resolve_includes(json_value, base_dir_of(name))?
+        } else {
+            json_value
+        };
 
         if let JsonValue::Object(obj) = json_value {
             if deep {
-                deep_merge_objects(&mut merged, obj);
+                deep_merge_objects(&mut merged, obj, "", name, &mut origins, strict, array_merge)?;
             } else {
-                for (key, value) in obj {
-                    merged.insert(key, value);
-                }
+                shallow_merge_objects(&mut merged, obj, name, &mut origins, strict)?;
             }
         } else {
             bail!("Cannot merge non-object HCL content from: {}", name);
@@ -197,22 +313,246 @@ fn merge_hcl_contents(contents: &[(String, String)], deep: bool) -> Result<JsonV
     Ok(JsonValue::Object(merged))
 }
 
+fn resolve_includes(value: JsonValue, base_dir: &Path) -> Result<JsonValue> {
+    let mut visited = HashSet::new();
+    resolve_includes_inner(value, base_dir, &mut visited)
+}
+
+fn resolve_includes_inner(
+    value: JsonValue,
+    base_dir: &Path,
+    visited: &mut HashSet<PathBuf>,
+) -> Result<JsonValue> {
+    let mut obj = match value {
+        JsonValue::Object(obj) => obj,
+        other => return Ok(other),
+    };
+
+    let includes = match obj.remove("include") {
+        Some(JsonValue::Array(paths)) => paths,
+        Some(_) => bail!("'include' must be an array of file paths"),
+        None => return Ok(JsonValue::Object(obj)),
+    };
+
+    let mut merged = serde_json::Map::new();
+    let mut origins: HashMap<String, String> = HashMap::new();
+
+    for include_path in includes {
+        let include_path = include_path
+            .as_str()
+            .context("'include' entries must be strings")?;
+        let resolved = base_dir.join(include_path);
+        let canonical = fs::canonicalize(&resolved)
+            .with_context(|| format!("Failed to resolve include: {}", resolved.display()))?;
+
+        if !visited.insert(canonical.clone()) {
+            bail!("Include cycle detected at {}", resolved.display());
+        }
+
+        let include_content = fs::read_to_string(&resolved)
+            .with_context(|| format!("Failed to read included file: {}", resolved.display()))?;
+        let include_value: Value = hcl::from_str(&include_content)
+            .with_context(|| format!("Failed to parse included file: {}", resolved.display()))?;
+        let include_json = hcl_to_json(include_value)?;
+        let include_base = resolved.parent().unwrap_or(Path::new("."));
+        let include_json = resolve_includes_inner(include_json, include_base, visited)?;
+
+        visited.remove(&canonical);
+
+        match include_json {
+            JsonValue::Object(include_obj) => {
+                deep_merge_objects(
+                    &mut merged,
+                    include_obj,
+                    "",
+                    include_path,
+                    &mut origins,
+                    false,
+                    ArrayMergeStrategy::Replace,
+                )?;
+            }
+            _ => bail!("Included file must contain an HCL object: {}", resolved.display()),
+        }
+    }
+
+    deep_merge_objects(
+        &mut merged,
+        obj,
+        "",
+        "(including file)",
+        &mut origins,
+        false,
+        ArrayMergeStrategy::Replace,
+    )?;
+
+    Ok(JsonValue::Object(merged))
+}
+
+fn register_origins(
+    value: &JsonValue,
+    path: &str,
+    source_file: &str,
+    origins: &mut HashMap<String, String>,
+) {
+    origins.insert(path.to_string(), source_file.to_string());
+
+    if let JsonValue::Object(map) = value {
+        for (key, child) in map {
+            let child_path = format!("{}.{}", path, key);
+            register_origins(child, &child_path, source_file, origins);
+        }
+    }
+}
+
+fn merge_arrays(target: &mut Vec<JsonValue>, source: Vec<JsonValue>, strategy: ArrayMergeStrategy) {
+    match strategy {
+        ArrayMergeStrategy::Replace => *target = source,
+        ArrayMergeStrategy::Append => target.extend(source),
+        ArrayMergeStrategy::Union => {
+            for item in source {
+                if !target.iter().any(|existing| existing == &item) {
+                    target.push(item);
+                }
+            }
+        }
+    }
+}
+
+fn shallow_merge_objects(
+    target: &mut serde_json::Map<String, JsonValue>,
+    source: serde_json::Map<String, JsonValue>,
+    source_file: &str,
+    origins: &mut HashMap<String, String>,
+    strict: bool,
+) -> Result<()> {
+    for (key, value) in source {
+        if strict {
+            if let Some(existing) = target.get(&key) {
+                if existing != &value {
+                    let original_file = origins
+                        .get(&key)
+                        .map(String::as_str)
+                        .unwrap_or("<unknown>");
+                    bail!(
+                        "conflicting values for '{}' in {} and {}",
+                        key, original_file, source_file
+                    );
+                }
+            }
+        }
+        origins.insert(key.clone(), source_file.to_string());
+        target.insert(key, value);
+    }
+    Ok(())
+}
+
 fn deep_merge_objects(
     target: &mut serde_json::Map<String, JsonValue>,
     source: serde_json::Map<String, JsonValue>,
-) {
+    path: &str,
+    source_file: &str,
+    origins: &mut HashMap<String, String>,
+    strict: bool,
+    array_merge: ArrayMergeStrategy,
+) -> Result<()> {
     for (key, value) in source {
+        let key_path = if path.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", path, key)
+        };
+
         match (target.get_mut(&key), &value) {
             (Some(JsonValue::Object(target_obj)), JsonValue::Object(source_obj)) => {
-                deep_merge_objects(target_obj, source_obj.clone());
+                deep_merge_objects(
+                    target_obj,
+                    source_obj.clone(),
+                    &key_path,
+                    source_file,
+                    origins,
+                    strict,
+                    array_merge,
+                )?;
+                continue;
             }
-            _ => {
-                target.insert(key, value);
+            (Some(JsonValue::Array(target_arr)), JsonValue::Array(_))
+                if array_merge != ArrayMergeStrategy::Replace =>
+            {
+                if let JsonValue::Array(source_arr) = value {
+                    merge_arrays(target_arr, source_arr, array_merge);
+                }
+                origins.insert(key_path, source_file.to_string());
+                continue;
             }
+            (Some(existing), _) if strict && existing != &value => {
+                let original_file = origins
+                    .get(&key_path)
+                    .map(String::as_str)
+                    .unwrap_or("<unknown>");
+                bail!(
+                    "conflicting values for '{}' in {} and {}",
+                    key_path, original_file, source_file
+                );
+            }
+            _ => {}
         }
+
+        register_origins(&value, &key_path, source_file, origins);
+        target.insert(key, value);
+    }
+    Ok(())
+}
+
+fn format_output(value: &JsonValue, args: &Args) -> Result<String> {
+    match args.to {
+        OutputFormat::Json => format_json(value, args),
+        OutputFormat::Yaml => serde_yaml::to_string(value).context("Failed to serialize to YAML"),
+        OutputFormat::Toml => toml::to_string(value).context("Failed to serialize to TOML"),
+        OutputFormat::Nix => Ok(format_nix(value, 0)),
     }
 }
 
+fn format_nix(value: &JsonValue, indent: usize) -> String {
+    match value {
+        JsonValue::Null => "null".to_string(),
+        JsonValue::Bool(b) => b.to_string(),
+        JsonValue::Number(n) => n.to_string(),
+        JsonValue::String(s) => format!("\"{}\"", escape_nix_string(s)),
+        JsonValue::Array(arr) => {
+            if arr.is_empty() {
+                "[ ]".to_string()
+            } else {
+                let inner_indent = "  ".repeat(indent + 1);
+                let items: Vec<String> = arr
+                    .iter()
+                    .map(|item| format!("{}{}", inner_indent, format_nix(item, indent + 1)))
+                    .collect();
+                format!("[\n{}\n{}]", items.join("\n"), "  ".repeat(indent))
+            }
+        }
+        JsonValue::Object(obj) => {
+            if obj.is_empty() {
+                "{ }".to_string()
+            } else {
+                let inner_indent = "  ".repeat(indent + 1);
+                let items: Vec<String> = obj
+                    .iter()
+                    .map(|(k, v)| format!("{}{} = {};", inner_indent, k, format_nix(v, indent + 1)))
+                    .collect();
+                format!("{{\n{}\n{}}}", items.join("\n"), "  ".repeat(indent))
+            }
+        }
+    }
+}
+
+fn escape_nix_string(s: &str) -> String {
+    // `$` must be escaped too, or a literal `${...}` (common in HCL/Terraform
+    // template strings) is interpreted by Nix as string interpolation.
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('$', "\\$")
+}
+
 fn format_json(value: &JsonValue, args: &Args) -> Result<String> {
     if args.pretty {
         let pretty = serde_json::to_string_pretty(value)?;
@@ -270,6 +610,54 @@ fn hcl_to_json(value: Value) -> Result<JsonValue> {
     }
 }
 
+fn apply_overrides(mut value: JsonValue, overrides: &[String]) -> Result<JsonValue> {
+    if overrides.is_empty() {
+        return Ok(value);
+    }
+
+    let map = value
+        .as_object_mut()
+        .context("Cannot apply --set overrides to non-object content")?;
+
+    for entry in overrides {
+        let (key, raw_value) = entry
+            .split_once('=')
+            .with_context(|| format!("Invalid --set argument (expected key=value): {}", entry))?;
+        let path: Vec<&str> = key.split('.').collect();
+        nested_set(map, &path, parse_set_value(raw_value));
+    }
+
+    Ok(value)
+}
+
+fn parse_set_value(raw: &str) -> JsonValue {
+    serde_json::from_str(raw).unwrap_or_else(|_| JsonValue::String(raw.to_string()))
+}
+
+fn nested_set(map: &mut serde_json::Map<String, JsonValue>, path: &[&str], value: JsonValue) {
+    let (head, rest) = match path.split_first() {
+        Some(parts) => parts,
+        None => return,
+    };
+
+    if rest.is_empty() {
+        map.insert((*head).to_string(), value);
+        return;
+    }
+
+    let entry = map
+        .entry((*head).to_string())
+        .or_insert_with(|| JsonValue::Object(serde_json::Map::new()));
+
+    if !entry.is_object() {
+        *entry = JsonValue::Object(serde_json::Map::new());
+    }
+
+    if let JsonValue::Object(nested_map) = entry {
+        nested_set(nested_map, rest, value);
+    }
+}
+
 fn extract_property(json: &JsonValue, property: &str) -> Result<JsonValue> {
     let parts: Vec<&str> = property.split('.').collect();
     let mut current = json;
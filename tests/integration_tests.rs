@@ -217,6 +217,247 @@ fn test_deep_merge_multiple_files() -> Result<()> {
     
     Ok(())
 }
+#[test]
+fn test_strict_merge_conflict() -> Result<()> {
+    let output = Command::new("cargo")
+        .args([
+            "run", "--", "--strict-merge", "--deep-merge",
+            "--file", "test_data/config1.tfvars",
+            "--file", "test_data/config_conflict.tfvars",
+        ])
+        .output()?;
+
+    assert!(!output.status.success());
+
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(stderr.contains("conflicting values for 'shared_config.database.engine'"));
+    assert!(stderr.contains("test_data/config1.tfvars"));
+    assert!(stderr.contains("test_data/config_conflict.tfvars"));
+
+    Ok(())
+}
+
+#[test]
+fn test_strict_merge_no_conflict() -> Result<()> {
+    let output = Command::new("cargo")
+        .args([
+            "run", "--", "--strict-merge", "--deep-merge",
+            "--file", "test_data/config1.tfvars",
+            "--file", "test_data/config2.tfvars",
+        ])
+        .output()?;
+
+    assert!(output.status.success());
+
+    let json_str = String::from_utf8(output.stdout)?;
+    let json: JsonValue = serde_json::from_str(&json_str)?;
+    assert_eq!(json["shared_config"]["database"]["engine"], "mysql");
+
+    Ok(())
+}
+
+#[test]
+fn test_array_merge_append() -> Result<()> {
+    let output = Command::new("cargo")
+        .args([
+            "run", "--", "--deep-merge", "--array-merge", "append",
+            "--file", "test_data/rules1.tfvars",
+            "--file", "test_data/rules2.tfvars",
+        ])
+        .output()?;
+
+    assert!(output.status.success());
+
+    let json_str = String::from_utf8(output.stdout)?;
+    let json: JsonValue = serde_json::from_str(&json_str)?;
+    assert_eq!(json["ingress_rules"].as_array().unwrap().len(), 4);
+
+    Ok(())
+}
+
+#[test]
+fn test_array_merge_union() -> Result<()> {
+    let output = Command::new("cargo")
+        .args([
+            "run", "--", "--deep-merge", "--array-merge", "union",
+            "--file", "test_data/rules1.tfvars",
+            "--file", "test_data/rules1_overlap.tfvars",
+        ])
+        .output()?;
+
+    assert!(output.status.success());
+
+    let json_str = String::from_utf8(output.stdout)?;
+    let json: JsonValue = serde_json::from_str(&json_str)?;
+    // rules1_overlap repeats one entry from rules1, union should drop the duplicate
+    assert_eq!(json["ingress_rules"].as_array().unwrap().len(), 3);
+
+    Ok(())
+}
+
+#[test]
+fn test_set_override() -> Result<()> {
+    let output = Command::new("cargo")
+        .args([
+            "run", "--",
+            "--file", "test_data/terraform.tfvars",
+            "--set", "database.port=5432",
+            "--set", "tags.Env=prod",
+        ])
+        .output()?;
+
+    assert!(output.status.success());
+
+    let json_str = String::from_utf8(output.stdout)?;
+    let json: JsonValue = serde_json::from_str(&json_str)?;
+    assert_eq!(json["database"]["port"], 5432);
+    assert_eq!(json["tags"]["Env"], "prod");
+    // existing keys on the merged document are left alone
+    assert_eq!(json["region"], "us-west-2");
+
+    Ok(())
+}
+
+#[test]
+fn test_from_file_manifest() -> Result<()> {
+    let manifest = NamedTempFile::new()?;
+    fs::write(
+        manifest.path(),
+        "test_data/terraform.tfvars\ntest_data/network.tfvars\n",
+    )?;
+
+    let output = Command::new("cargo")
+        .args([
+            "run", "--",
+            "--from-file", manifest.path().to_str().unwrap(),
+        ])
+        .output()?;
+
+    assert!(output.status.success());
+
+    let json_str = String::from_utf8(output.stdout)?;
+    let json: JsonValue = serde_json::from_str(&json_str)?;
+    assert_eq!(json["region"], "us-west-2");
+    assert_eq!(json["vpc_cidr"], "10.0.0.0/16");
+
+    Ok(())
+}
+
+#[test]
+fn test_depfile_output() -> Result<()> {
+    let depfile = NamedTempFile::new()?;
+
+    let output = Command::new("cargo")
+        .args([
+            "run", "--",
+            "--file", "test_data/terraform.tfvars",
+            "--depfile", depfile.path().to_str().unwrap(),
+        ])
+        .output()?;
+
+    assert!(output.status.success());
+
+    let depfile_contents = fs::read_to_string(depfile.path())?;
+    assert!(depfile_contents.starts_with("-: "));
+    assert!(depfile_contents.contains("test_data/terraform.tfvars"));
+
+    Ok(())
+}
+
+#[test]
+fn test_resolve_includes() -> Result<()> {
+    let output = Command::new("cargo")
+        .args([
+            "run", "--", "--resolve-includes",
+            "--file", "test_data/includes/app.tfvars",
+        ])
+        .output()?;
+
+    assert!(output.status.success());
+
+    let json_str = String::from_utf8(output.stdout)?;
+    let json: JsonValue = serde_json::from_str(&json_str)?;
+    // region comes from the included base.tfvars defaults
+    assert_eq!(json["region"], "us-west-2");
+    // app.tfvars overrides instance_type from the base default
+    assert_eq!(json["instance_type"], "t3.large");
+    assert!(json.get("include").is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_to_yaml() -> Result<()> {
+    let output = Command::new("cargo")
+        .args([
+            "run", "--", "--to", "yaml",
+            "--file", "test_data/terraform.tfvars",
+            "--property", "region",
+        ])
+        .output()?;
+
+    assert!(output.status.success());
+
+    let yaml_str = String::from_utf8(output.stdout)?;
+    assert_eq!(yaml_str.trim(), "us-west-2");
+
+    Ok(())
+}
+
+#[test]
+fn test_to_toml() -> Result<()> {
+    let output = Command::new("cargo")
+        .args([
+            "run", "--", "--to", "toml",
+            "--file", "test_data/terraform.tfvars",
+            "--property", "tags",
+        ])
+        .output()?;
+
+    assert!(output.status.success());
+
+    let toml_str = String::from_utf8(output.stdout)?;
+    assert!(toml_str.contains("Environment = \"production\""));
+
+    Ok(())
+}
+
+#[test]
+fn test_to_toml_whole_document_failure_is_clear_error() -> Result<()> {
+    // TOML requires table keys (e.g. `tags`) to come after all scalar keys at
+    // the same level; a whole tfvars document with a scalar declared after an
+    // object commonly can't round-trip. Conversion may succeed; if it fails,
+    // make sure the failure is a clear error instead of a panic.
+    let output = Command::new("cargo")
+        .args(["run", "--", "--to", "toml", "--file", "test_data/terraform.tfvars"])
+        .output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8(output.stderr)?;
+        assert!(stderr.contains("Failed to serialize to TOML"));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_to_nix() -> Result<()> {
+    let output = Command::new("cargo")
+        .args([
+            "run", "--", "--to", "nix",
+            "--file", "test_data/terraform.tfvars",
+            "--property", "region",
+        ])
+        .output()?;
+
+    assert!(output.status.success());
+
+    let nix_str = String::from_utf8(output.stdout)?;
+    assert_eq!(nix_str.trim(), "\"us-west-2\"");
+
+    Ok(())
+}
+
 #[test]
 fn test_single_quotes_with_embedded_quotes() -> Result<()> {
     let output = Command::new("cargo")